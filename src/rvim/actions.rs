@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use crossterm::event::{KeyCode, KeyModifiers};
+
+use super::Editor;
+
+/// Which set of bindings a keypress should be looked up in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    Normal,
+    Insert,
+}
+
+pub type Action = fn(&mut Editor);
+
+type ActionKey = (Mode, KeyCode, KeyModifiers);
+
+/// A key -> action lookup table, populated once at startup. Rebinding a
+/// key is just registering a different `Action` under the same
+/// `(Mode, KeyCode, KeyModifiers)` triple instead of editing a match arm.
+pub struct ActionMap(HashMap<ActionKey, Action>);
+
+impl ActionMap {
+    pub fn get(&self, mode: Mode, code: KeyCode, modifiers: KeyModifiers) -> Option<Action> {
+        self.0.get(&(mode, code, modifiers)).copied()
+    }
+}
+
+/// Builds the default keybindings.
+pub fn load_actions() -> ActionMap {
+    use KeyCode::*;
+    use Mode::{Insert, Normal};
+
+    let mut actions: HashMap<ActionKey, Action> = HashMap::new();
+
+    actions.insert((Normal, Char('q'), KeyModifiers::NONE), quit as Action);
+    actions.insert((Normal, Char('e'), KeyModifiers::NONE), enter_insert);
+    actions.insert((Normal, Char('s'), KeyModifiers::NONE), move_down);
+    actions.insert((Normal, Char('w'), KeyModifiers::NONE), move_up);
+    actions.insert((Normal, Char('a'), KeyModifiers::NONE), move_left);
+    actions.insert((Normal, Char('d'), KeyModifiers::NONE), move_right);
+
+    // Word motions ride the modifiers that the WASD-style movement keys
+    // leave free: Ctrl for word-wise, Alt for WORD-wise (any run of
+    // non-whitespace).
+    actions.insert((Normal, Char('w'), KeyModifiers::CONTROL), next_word_start);
+    actions.insert((Normal, Char('b'), KeyModifiers::CONTROL), prev_word_start);
+    actions.insert((Normal, Char('e'), KeyModifiers::CONTROL), next_word_end);
+    actions.insert((Normal, Char('w'), KeyModifiers::ALT), next_word_start_long);
+    actions.insert((Normal, Char('b'), KeyModifiers::ALT), prev_word_start_long);
+    actions.insert((Normal, Char('e'), KeyModifiers::ALT), next_word_end_long);
+
+    actions.insert((Normal, Char('u'), KeyModifiers::NONE), undo);
+    actions.insert((Normal, Char('r'), KeyModifiers::CONTROL), redo);
+
+    actions.insert(
+        (Normal, Char('n'), KeyModifiers::CONTROL),
+        toggle_relative_line_numbers,
+    );
+
+    actions.insert((Insert, Esc, KeyModifiers::NONE), exit_insert);
+    actions.insert((Insert, Backspace, KeyModifiers::NONE), backspace);
+
+    ActionMap(actions)
+}
+
+fn quit(editor: &mut Editor) {
+    editor.running = false;
+}
+
+fn enter_insert(editor: &mut Editor) {
+    editor.mode = Mode::Insert;
+}
+
+fn exit_insert(editor: &mut Editor) {
+    editor.mode = Mode::Normal;
+
+    if let Some(file_path) = editor.file_path.clone() {
+        if let Err(err) = editor.save_to_file(&file_path) {
+            eprintln!("ERROR: could not save {}: {}", file_path, err);
+        }
+    }
+}
+
+fn backspace(editor: &mut Editor) {
+    editor.remove_char();
+}
+
+fn move_down(editor: &mut Editor) {
+    let line = editor.current_line();
+    let column = editor.cursor - editor.lines[line].begin;
+
+    if line < editor.lines.len() - 1 {
+        editor.cursor = editor.lines[line + 1].begin + column;
+
+        if editor.cursor > editor.lines[line + 1].end {
+            editor.cursor = editor.lines[line + 1].end;
+        }
+    }
+}
+
+fn move_up(editor: &mut Editor) {
+    let line = editor.current_line();
+    let column = editor.cursor - editor.lines[line].begin;
+
+    if line > 0 {
+        editor.cursor = editor.lines[line - 1].begin + column;
+
+        if editor.cursor > editor.lines[line - 1].end {
+            editor.cursor = editor.lines[line - 1].end;
+        }
+    }
+}
+
+fn move_left(editor: &mut Editor) {
+    if editor.cursor > 0 {
+        editor.cursor -= 1;
+    }
+}
+
+fn move_right(editor: &mut Editor) {
+    if !editor.data.is_empty() && editor.cursor < editor.data.len() - 1 {
+        editor.cursor += 1;
+    }
+}
+
+fn next_word_start(editor: &mut Editor) {
+    editor.move_next_word_start(false);
+}
+
+fn next_word_start_long(editor: &mut Editor) {
+    editor.move_next_word_start(true);
+}
+
+fn prev_word_start(editor: &mut Editor) {
+    editor.move_prev_word_start(false);
+}
+
+fn prev_word_start_long(editor: &mut Editor) {
+    editor.move_prev_word_start(true);
+}
+
+fn next_word_end(editor: &mut Editor) {
+    editor.move_next_word_end(false);
+}
+
+fn next_word_end_long(editor: &mut Editor) {
+    editor.move_next_word_end(true);
+}
+
+fn undo(editor: &mut Editor) {
+    editor.undo();
+}
+
+fn redo(editor: &mut Editor) {
+    editor.redo();
+}
+
+fn toggle_relative_line_numbers(editor: &mut Editor) {
+    editor.relative_line_numbers = !editor.relative_line_numbers;
+}
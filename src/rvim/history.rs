@@ -0,0 +1,185 @@
+use super::Editor;
+
+/// A single reversible edit.
+#[derive(Debug, Clone, Copy)]
+enum Edit {
+    Insert { pos: usize, ch: char },
+    Delete { pos: usize, ch: char },
+}
+
+/// A run of edits that should undo/redo together, e.g. the characters of
+/// one typed word.
+#[derive(Debug, Default)]
+struct EditGroup {
+    edits: Vec<Edit>,
+}
+
+#[derive(Debug, Default)]
+pub struct History {
+    undo_stack: Vec<EditGroup>,
+    redo_stack: Vec<EditGroup>,
+}
+
+impl History {
+    /// Records `edit`, coalescing it into the current group when it's the
+    /// same kind of edit at the position immediately next to the last one
+    /// (i.e. still typing/backspacing in one direction).
+    fn push(&mut self, edit: Edit) {
+        self.redo_stack.clear();
+
+        if let Some(group) = self.undo_stack.last_mut() {
+            if let Some(&last) = group.edits.last() {
+                let coalesces = match (last, edit) {
+                    (Edit::Insert { pos: last_pos, .. }, Edit::Insert { pos, .. }) => {
+                        pos == last_pos + 1
+                    }
+                    (Edit::Delete { pos: last_pos, .. }, Edit::Delete { pos, .. }) => {
+                        pos + 1 == last_pos
+                    }
+                    _ => false,
+                };
+
+                if coalesces {
+                    group.edits.push(edit);
+                    return;
+                }
+            }
+        }
+
+        self.undo_stack.push(EditGroup { edits: vec![edit] });
+    }
+}
+
+impl Editor {
+    pub(super) fn record_insert(&mut self, pos: usize, ch: char) {
+        self.history.push(Edit::Insert { pos, ch });
+    }
+
+    pub(super) fn record_delete(&mut self, pos: usize, ch: char) {
+        self.history.push(Edit::Delete { pos, ch });
+    }
+
+    pub fn undo(&mut self) {
+        let Some(group) = self.history.undo_stack.pop() else {
+            return;
+        };
+
+        for &edit in group.edits.iter().rev() {
+            match edit {
+                Edit::Insert { pos, .. } => {
+                    self.apply_remove_char_at(pos);
+                    self.cursor = pos;
+                }
+                Edit::Delete { pos, ch } => {
+                    self.apply_insert_char_at(pos, ch);
+                    self.cursor = pos;
+                }
+            }
+        }
+
+        self.history.redo_stack.push(group);
+    }
+
+    pub fn redo(&mut self) {
+        let Some(group) = self.history.redo_stack.pop() else {
+            return;
+        };
+
+        for &edit in group.edits.iter() {
+            match edit {
+                Edit::Insert { pos, ch } => {
+                    self.apply_insert_char_at(pos, ch);
+                    self.cursor = pos + 1;
+                }
+                Edit::Delete { pos, .. } => {
+                    self.apply_remove_char_at(pos);
+                    self.cursor = pos;
+                }
+            }
+        }
+
+        self.history.undo_stack.push(group);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Edit, History};
+
+    fn group_sizes(history: &History) -> Vec<usize> {
+        history
+            .undo_stack
+            .iter()
+            .map(|group| group.edits.len())
+            .collect()
+    }
+
+    #[test]
+    fn adjacent_inserts_coalesce_into_one_group() {
+        let mut history = History::default();
+
+        history.push(Edit::Insert { pos: 0, ch: 'h' });
+        history.push(Edit::Insert { pos: 1, ch: 'i' });
+
+        assert_eq!(group_sizes(&history), vec![2]);
+    }
+
+    #[test]
+    fn adjacent_deletes_coalesce_into_one_group() {
+        let mut history = History::default();
+
+        // Backspacing "hi" deletes position 1 then position 0.
+        history.push(Edit::Delete { pos: 1, ch: 'i' });
+        history.push(Edit::Delete { pos: 0, ch: 'h' });
+
+        assert_eq!(group_sizes(&history), vec![2]);
+    }
+
+    #[test]
+    fn non_adjacent_inserts_start_a_new_group() {
+        let mut history = History::default();
+
+        history.push(Edit::Insert { pos: 0, ch: 'h' });
+        history.push(Edit::Insert { pos: 5, ch: 'i' });
+
+        assert_eq!(group_sizes(&history), vec![1, 1]);
+    }
+
+    #[test]
+    fn insert_immediately_followed_by_delete_does_not_coalesce() {
+        let mut history = History::default();
+
+        history.push(Edit::Insert { pos: 0, ch: 'h' });
+        history.push(Edit::Delete { pos: 0, ch: 'h' });
+
+        assert_eq!(group_sizes(&history), vec![1, 1]);
+    }
+
+    #[test]
+    fn two_separate_typing_bursts_stay_in_separate_groups() {
+        let mut history = History::default();
+
+        history.push(Edit::Insert { pos: 0, ch: 'h' });
+        history.push(Edit::Insert { pos: 1, ch: 'i' });
+
+        // A motion/backspace elsewhere breaks the run; typing resumes at an
+        // unrelated position and should not glue onto the first burst.
+        history.push(Edit::Insert { pos: 10, ch: 'x' });
+        history.push(Edit::Insert { pos: 11, ch: 'y' });
+
+        assert_eq!(group_sizes(&history), vec![2, 2]);
+    }
+
+    #[test]
+    fn pushing_clears_the_redo_stack() {
+        let mut history = History::default();
+
+        history.push(Edit::Insert { pos: 0, ch: 'h' });
+        history.redo_stack.push(history.undo_stack.pop().unwrap());
+        assert_eq!(history.redo_stack.len(), 1);
+
+        history.push(Edit::Insert { pos: 0, ch: 'x' });
+
+        assert!(history.redo_stack.is_empty());
+    }
+}
@@ -1,27 +1,75 @@
+mod actions;
+mod history;
+mod motion;
+mod piece_table;
+
 use std::{
     fs::File,
     io::{stdout, BufWriter, Error, Write},
-    sync::Mutex,
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError},
+        Mutex,
+    },
+    thread,
+    time::Duration,
 };
 
 use crossterm::{
     cursor::MoveTo,
-    event::{read, Event, KeyCode, KeyEvent, KeyModifiers},
+    event::{poll, read, Event, KeyCode, KeyEvent, KeyModifiers},
     terminal::{enable_raw_mode, size, Clear, ClearType},
     ExecutableCommand,
 };
 use once_cell::sync::Lazy;
 use termios::{tcgetattr, tcsetattr, Termios, ECHO, ICANON};
 
+use actions::{load_actions, ActionMap, Mode};
+use history::History;
+use piece_table::PieceTable;
+
 pub static EDITOR: Lazy<Mutex<Editor>> = Lazy::new(|| {
     Mutex::new(Editor {
-        data: vec![],
+        data: PieceTable::new(&""),
         lines: vec![],
         cursor: 0,
         view_row: 0,
+        history: History::default(),
+        event_rx: None,
+        relative_line_numbers: false,
+        mode: Mode::Normal,
+        running: true,
+        file_path: None,
+        actions: load_actions(),
     })
 });
 
+/// How often the input thread checks for a pending terminal event.
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Spawns a dedicated thread that polls crossterm for events and forwards
+/// them over a channel, so the main loop is never blocked on `read()` and
+/// can still repaint on resize or on a timer.
+fn spawn_input_thread() -> Receiver<Event> {
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || loop {
+        match poll(POLL_INTERVAL) {
+            Ok(true) => match read() {
+                Ok(event) => {
+                    if tx.send(event).is_err() {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            },
+            Ok(false) => {}
+            Err(_) => break,
+        }
+    });
+
+    rx
+}
+
 #[derive(Debug)]
 pub struct Line {
     begin: usize,
@@ -34,19 +82,28 @@ impl Line {
     }
 }
 
-#[derive(Debug)]
 pub struct Editor {
-    data: Vec<char>,
+    data: PieceTable,
     lines: Vec<Line>,
     cursor: usize,
     view_row: usize,
+    history: History,
+    event_rx: Option<Receiver<Event>>,
+    /// Number the gutter relative to the cursor line (vim's `relativenumber`)
+    /// instead of showing every line's absolute number.
+    relative_line_numbers: bool,
+    mode: Mode,
+    running: bool,
+    file_path: Option<String>,
+    actions: ActionMap,
 }
 
 impl Editor {
     pub fn reset(&mut self) {
-        self.data.clear();
+        self.data = PieceTable::new(&"");
         self.lines.clear();
         self.cursor = 0;
+        self.history = History::default();
     }
 
     pub fn init<T>(&mut self, content: &T)
@@ -55,15 +112,15 @@ impl Editor {
     {
         self.reset();
 
-        for char in content.as_ref().chars() {
-            self.data.push(char);
-        }
+        self.data = PieceTable::new(content);
     }
 
     fn recompute_size(&mut self) {
+        self.lines.clear();
+
         let mut begin = 0;
 
-        for (i, &char) in self.data.iter().enumerate() {
+        for (i, char) in self.data.iter().enumerate() {
             if char == '\n' {
                 self.lines.push(Line::new(begin, i));
 
@@ -74,25 +131,88 @@ impl Editor {
         self.lines.push(Line::new(begin, self.data.len()));
     }
 
+    /// Shifts every line starting after `from` by `delta`, without
+    /// rescanning the pieces that produced them.
+    fn shift_lines_after(&mut self, from: usize, delta: isize) {
+        for line in self.lines.iter_mut().skip(from + 1) {
+            line.begin = (line.begin as isize + delta) as usize;
+            line.end = (line.end as isize + delta) as usize;
+        }
+    }
+
+    /// Inserts `char` at `pos` and patches the line index in place, without
+    /// touching history or the cursor. `insert_char` and undo/redo each
+    /// layer their own bookkeeping on top of this.
+    fn apply_insert_char_at(&mut self, pos: usize, char: char) {
+        let line = self.line_at(pos);
+
+        self.data.insert(pos, char);
+
+        if char == '\n' {
+            let Line { begin, end } = self.lines[line];
+            self.lines[line] = Line::new(begin, pos);
+            self.lines.insert(line + 1, Line::new(pos + 1, end + 1));
+
+            // Both `line` and the freshly split `line + 1` already hold
+            // final positions, so only lines after that need shifting.
+            self.shift_lines_after(line + 1, 1);
+        } else {
+            self.lines[line].end += 1;
+
+            self.shift_lines_after(line, 1);
+        }
+    }
+
+    /// Removes the char at `pos`, patches the line index in place, and
+    /// returns the removed char so the caller can record it for undo.
+    fn apply_remove_char_at(&mut self, pos: usize) -> char {
+        let line = self.line_at(pos);
+        let removed = self.data.remove(pos);
+
+        if removed == '\n' {
+            let Line { begin, .. } = self.lines[line];
+            let Line { end, .. } = self.lines.remove(line + 1);
+            self.lines[line] = Line::new(begin, end - 1);
+        } else {
+            self.lines[line].end -= 1;
+        }
+
+        self.shift_lines_after(line, -1);
+
+        removed
+    }
+
     fn insert_char(&mut self, char: char) {
-        self.data.insert(self.cursor, char);
+        let pos = self.cursor;
+
+        self.apply_insert_char_at(pos, char);
+        self.record_insert(pos, char);
+
         self.cursor += 1;
-        self.recompute_size();
     }
 
     fn remove_char(&mut self) {
         if self.cursor > 0 {
-            self.data.remove(self.cursor);
-            self.cursor -= 1;
-            self.recompute_size();
+            let pos = self.cursor - 1;
+            let removed = self.apply_remove_char_at(pos);
+            self.record_delete(pos, removed);
+
+            self.cursor = pos;
         }
     }
 
-    fn current_line(&self) -> usize {
-        assert!(self.cursor <= self.data.len() - 1);
+    fn line_at(&self, pos: usize) -> usize {
+        if self.data.is_empty() {
+            return 0;
+        }
+
+        // `pos == data.len()` is the valid "one past the last char" cursor
+        // produced by appending at the end of the buffer; the last `Line`'s
+        // `end` already covers it.
+        assert!(pos <= self.data.len());
 
         for (i, line) in self.lines.iter().enumerate() {
-            if line.begin <= self.cursor && self.cursor <= line.end {
+            if line.begin <= pos && pos <= line.end {
                 return i;
             }
         }
@@ -100,15 +220,27 @@ impl Editor {
         0
     }
 
+    fn current_line(&self) -> usize {
+        self.line_at(self.cursor)
+    }
+
+    /// Width of the line-number gutter, including its separator column.
+    fn gutter_width(&self) -> usize {
+        self.lines.len().to_string().len() + 1
+    }
+
     fn rerender(&mut self, insert: bool) -> Result<(), Error> {
         let mut stdout = stdout();
         stdout
-            .execute(Clear(ClearType::Purge))? 
+            .execute(Clear(ClearType::Purge))?
             .execute(MoveTo(0, 0))?;
 
-        // TODO: We should store history first then recover it after rvim finished.
+        // TODO: `history` only lives in memory; persist it to disk so undo
+        // history survives quitting and reopening the file.
 
-        let (width, height) = size().map(|(w, h)| (w as usize, h as usize))?;
+        let (total_width, height) = size().map(|(w, h)| (w as usize, h as usize))?;
+        let gutter_width = self.gutter_width();
+        let width = total_width.saturating_sub(gutter_width);
         let cursor_column = self.current_line();
         let mut cursor_row = self.cursor - self.lines[cursor_column].begin;
 
@@ -131,15 +263,24 @@ impl Editor {
                     line_size = width;
                 }
 
+                let number = if self.relative_line_numbers && row != cursor_column {
+                    row.abs_diff(cursor_column)
+                } else {
+                    row + 1
+                };
+
                 write!(
                     &mut stdout,
-                    "{}\r\n",
-                    self.data[begin..begin + line_size]
+                    "{:>digits$} {}\r\n",
+                    number,
+                    self.data
+                        .chars_range(begin, begin + line_size)
                         .iter()
-                        .collect::<String>()
+                        .collect::<String>(),
+                    digits = gutter_width - 1,
                 )?;
             } else {
-                write!(&mut stdout, "~\r\n")?;
+                write!(&mut stdout, "{}~\r\n", " ".repeat(gutter_width))?;
             }
         }
 
@@ -148,7 +289,7 @@ impl Editor {
         }
 
         stdout.execute(MoveTo(
-            (cursor_row) as u16,
+            (cursor_row + gutter_width) as u16,
             (cursor_column - self.view_row) as u16,
         ))?;
 
@@ -159,7 +300,7 @@ impl Editor {
         let file = File::create(file_path)?;
         let mut buf_writer = BufWriter::new(file);
 
-        for char in &self.data {
+        for char in self.data.iter() {
             buf_writer.write_all(char.to_string().as_bytes())?;
         }
 
@@ -170,8 +311,9 @@ impl Editor {
 
     pub fn start_interactive<'a>(&mut self, file_path: &'a str) -> Result<(), Error> {
         let mut termios = Termios::from_fd(0)?;
-        let mut quit = false;
-        let mut insert = false;
+        self.running = true;
+        self.mode = Mode::Normal;
+        self.file_path = Some(file_path.to_string());
 
         if let Err(err) = tcgetattr(0, &mut termios) {
             println!("ERROR: Could not get status of terminal");
@@ -190,87 +332,28 @@ impl Editor {
 
         self.recompute_size();
 
-        while !quit {
-            self.rerender(insert)?;
-
-            if insert {
-                match read()? {
-                    Event::Key(KeyEvent {
-                        code: KeyCode::Esc,
-                        modifiers: KeyModifiers::NONE,
-                        kind: _,
-                        state: _,
-                    }) => {
-                        insert = false;
-
-                        self.save_to_file(file_path)?;
-                    }
-                    Event::Key(KeyEvent {
-                        code: KeyCode::Backspace,
-                        modifiers: KeyModifiers::NONE,
-                        kind: _,
-                        state: _,
-                    }) => {
-                        self.remove_char();
-                    }
-                    Event::Key(KeyEvent {
-                        code: KeyCode::Char(key_code),
-                        modifiers: KeyModifiers::NONE,
-                        kind: _,
-                        state: _,
-                    }) => {
-                        self.insert_char(key_code);
-                    }
-                    _ => {}
-                };
-            } else {
-                match read()? {
-                    Event::Key(KeyEvent {
-                        code: KeyCode::Char(code),
-                        modifiers: _,
-                        kind: _,
-                        state: _,
-                    }) => match code {
-                        'q' => quit = true,
-                        'e' => insert = true,
-                        's' => {
-                            let line = self.current_line();
-                            let column = self.cursor - self.lines[line].begin;
-
-                            if line < self.lines.len() - 1 {
-                                self.cursor = self.lines[line + 1].begin + column;
-
-                                if self.cursor > self.lines[line + 1].end {
-                                    self.cursor = self.lines[line + 1].end;
-                                }
-                            }
-                        }
-                        'w' => {
-                            let line = self.current_line();
-                            let column = self.cursor - self.lines[line].begin;
-
-                            if line > 0 {
-                                self.cursor = self.lines[line + 1].begin + column;
-
-                                if self.cursor > self.lines[line + 1].end {
-                                    self.cursor = self.lines[line + 1].end;
-                                }
-                            }
-                        }
-                        'a' => {
-                            if self.cursor > 0 {
-                                self.cursor -= 1;
-                            }
-                        }
-                        'd' => {
-                            if self.cursor < self.data.len() - 1 {
-                                self.cursor += 1;
-                            }
-                        }
-                        _ => {}
-                    },
-                    _ => {}
-                }
+        let event_rx = spawn_input_thread();
+        self.event_rx = Some(event_rx);
+
+        while self.running {
+            self.rerender(self.mode == Mode::Insert)?;
+
+            // Block for at most one repaint tick, then drain whatever else
+            // piled up in the meantime so fast typing/paste doesn't lag
+            // behind one redraw per keystroke.
+            match self
+                .event_rx
+                .as_ref()
+                .unwrap()
+                .recv_timeout(POLL_INTERVAL * 20)
+            {
+                Ok(event) => self.handle_event(event),
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+
+            while let Ok(event) = self.event_rx.as_ref().unwrap().try_recv() {
+                self.handle_event(event);
             }
         }
 
@@ -280,4 +363,30 @@ impl Editor {
 
         Ok(())
     }
+
+    /// Looks up `event` in the action map for the current mode and runs it.
+    /// A plain character typed in insert mode has no fixed binding (every
+    /// char would need its own entry), so it falls back to `insert_char`
+    /// directly when nothing more specific is bound.
+    fn handle_event(&mut self, event: Event) {
+        let Event::Key(KeyEvent {
+            code, modifiers, ..
+        }) = event
+        else {
+            // Resize needs no extra bookkeeping: `rerender` reads the
+            // terminal size fresh every call, so the next paint picks it up.
+            return;
+        };
+
+        if let Some(action) = self.actions.get(self.mode, code, modifiers) {
+            action(self);
+            return;
+        }
+
+        if self.mode == Mode::Insert {
+            if let (KeyCode::Char(ch), KeyModifiers::NONE) = (code, modifiers) {
+                self.insert_char(ch);
+            }
+        }
+    }
 }
@@ -0,0 +1,207 @@
+use super::Editor;
+
+/// The class a character belongs to for the purpose of word motions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Whitespace,
+    Word,
+    Punctuation,
+}
+
+impl CharClass {
+    /// Classifies `ch`. When `long` is set (a "WORD" motion), word
+    /// characters and punctuation collapse into a single non-whitespace
+    /// class, matching vim's WORD semantics.
+    fn of(ch: char, long: bool) -> Self {
+        if ch.is_whitespace() {
+            CharClass::Whitespace
+        } else if long || ch.is_alphanumeric() || ch == '_' {
+            CharClass::Word
+        } else {
+            CharClass::Punctuation
+        }
+    }
+}
+
+impl Editor {
+    fn char_class(&self, index: usize, long: bool) -> CharClass {
+        CharClass::of(self.data.char_at(index), long)
+    }
+
+    /// Moves the cursor to the start of the next word (or WORD).
+    pub fn move_next_word_start(&mut self, long: bool) {
+        let len = self.data.len();
+
+        if self.cursor >= len.saturating_sub(1) {
+            return;
+        }
+
+        let start_class = self.char_class(self.cursor, long);
+        let mut pos = self.cursor;
+
+        while pos < len - 1 && self.char_class(pos, long) == start_class {
+            pos += 1;
+        }
+
+        while pos < len - 1 && self.char_class(pos, long) == CharClass::Whitespace {
+            pos += 1;
+        }
+
+        self.cursor = pos;
+    }
+
+    /// Moves the cursor to the start of the previous word (or WORD).
+    pub fn move_prev_word_start(&mut self, long: bool) {
+        if self.cursor == 0 {
+            return;
+        }
+
+        let mut pos = self.cursor - 1;
+
+        while pos > 0 && self.char_class(pos, long) == CharClass::Whitespace {
+            pos -= 1;
+        }
+
+        let class = self.char_class(pos, long);
+
+        while pos > 0 && self.char_class(pos - 1, long) == class {
+            pos -= 1;
+        }
+
+        self.cursor = pos;
+    }
+
+    /// Moves the cursor to the end of the next word (or WORD).
+    pub fn move_next_word_end(&mut self, long: bool) {
+        let len = self.data.len();
+
+        if self.cursor >= len.saturating_sub(1) {
+            return;
+        }
+
+        let mut pos = self.cursor + 1;
+
+        while pos < len - 1 && self.char_class(pos, long) == CharClass::Whitespace {
+            pos += 1;
+        }
+
+        let class = self.char_class(pos, long);
+
+        while pos < len - 1 && self.char_class(pos + 1, long) == class {
+            pos += 1;
+        }
+
+        self.cursor = pos;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CharClass, Editor};
+    use crate::rvim::actions::{load_actions, Mode};
+    use crate::rvim::history::History;
+    use crate::rvim::piece_table::PieceTable;
+
+    fn editor_at(content: &str, cursor: usize) -> Editor {
+        Editor {
+            data: PieceTable::new(&content),
+            lines: vec![],
+            cursor,
+            view_row: 0,
+            history: History::default(),
+            event_rx: None,
+            relative_line_numbers: false,
+            mode: Mode::Normal,
+            running: true,
+            file_path: None,
+            actions: load_actions(),
+        }
+    }
+
+    #[test]
+    fn char_class_boundaries() {
+        assert_eq!(CharClass::of(' ', false), CharClass::Whitespace);
+        assert_eq!(CharClass::of('\n', false), CharClass::Whitespace);
+        assert_eq!(CharClass::of('a', false), CharClass::Word);
+        assert_eq!(CharClass::of('_', false), CharClass::Word);
+        assert_eq!(CharClass::of('9', false), CharClass::Word);
+        assert_eq!(CharClass::of('.', false), CharClass::Punctuation);
+        assert_eq!(CharClass::of(',', false), CharClass::Punctuation);
+
+        // WORD motions collapse word-chars and punctuation together.
+        assert_eq!(CharClass::of('.', true), CharClass::Word);
+        assert_eq!(CharClass::of(' ', true), CharClass::Whitespace);
+    }
+
+    #[test]
+    fn next_word_start_skips_punctuation_as_its_own_token() {
+        let mut editor = editor_at("foo.bar baz", 0);
+
+        editor.move_next_word_start(false);
+        assert_eq!(editor.cursor, 3); // "." (start of the punctuation run)
+
+        editor.move_next_word_start(false);
+        assert_eq!(editor.cursor, 4); // "bar"
+
+        editor.move_next_word_start(false);
+        assert_eq!(editor.cursor, 8); // "baz"
+    }
+
+    #[test]
+    fn next_word_start_long_treats_punctuation_as_part_of_the_word() {
+        let mut editor = editor_at("foo.bar baz", 0);
+
+        editor.move_next_word_start(true);
+        assert_eq!(editor.cursor, 8); // "baz", "foo.bar" is one WORD
+    }
+
+    #[test]
+    fn next_word_start_crosses_newline() {
+        let mut editor = editor_at("foo\nbar", 0);
+
+        editor.move_next_word_start(false);
+        assert_eq!(editor.cursor, 4); // "\n" counts as whitespace
+    }
+
+    #[test]
+    fn prev_word_start_is_symmetric() {
+        let mut editor = editor_at("foo.bar baz", 10);
+
+        editor.move_prev_word_start(false);
+        assert_eq!(editor.cursor, 8); // "baz"
+
+        editor.move_prev_word_start(false);
+        assert_eq!(editor.cursor, 4); // "bar"
+
+        editor.move_prev_word_start(false);
+        assert_eq!(editor.cursor, 3); // "."
+
+        editor.move_prev_word_start(false);
+        assert_eq!(editor.cursor, 0); // "foo"
+    }
+
+    #[test]
+    fn next_word_end_lands_on_last_char_of_the_next_token() {
+        // Cursor already sits on the last char of "foo", so `e` should jump
+        // ahead to the end of "bar" rather than stay put.
+        let mut editor = editor_at("foo bar baz", 2);
+
+        editor.move_next_word_end(false);
+        assert_eq!(editor.cursor, 6); // last char of "bar"
+    }
+
+    #[test]
+    fn motions_clamp_at_buffer_edges() {
+        let mut start = editor_at("foo", 2);
+        start.move_next_word_start(false);
+        assert_eq!(start.cursor, 2);
+
+        let mut prev = editor_at("foo", 0);
+        prev.move_prev_word_start(false);
+        assert_eq!(prev.cursor, 0);
+
+        let mut end = editor_at("foo", 2);
+        end.move_next_word_end(false);
+        assert_eq!(end.cursor, 2);
+    }
+}
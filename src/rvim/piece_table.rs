@@ -0,0 +1,300 @@
+/// Which backing buffer a [`Piece`] slices into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    Original,
+    Added,
+}
+
+/// A contiguous run of characters taken from either the `original` or
+/// `added` buffer of a [`PieceTable`].
+#[derive(Debug, Clone, Copy)]
+struct Piece {
+    source: Source,
+    start: usize,
+    len: usize,
+}
+
+/// A piece-table backed character buffer.
+///
+/// The buffer is never mutated in place: `original` holds the file as it
+/// was loaded and `added` is an append-only log of everything typed since.
+/// Edits only ever touch the `pieces` list, splitting or trimming at most a
+/// couple of entries, so both `insert` and `remove` are O(pieces) rather
+/// than O(len).
+#[derive(Debug)]
+pub struct PieceTable {
+    original: Vec<char>,
+    added: Vec<char>,
+    pieces: Vec<Piece>,
+}
+
+impl PieceTable {
+    pub fn new<T>(content: &T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        let original: Vec<char> = content.as_ref().chars().collect();
+        let len = original.len();
+
+        let pieces = if len == 0 {
+            vec![]
+        } else {
+            vec![Piece {
+                source: Source::Original,
+                start: 0,
+                len,
+            }]
+        };
+
+        Self {
+            original,
+            added: vec![],
+            pieces,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.pieces.iter().map(|piece| piece.len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn slice(&self, piece: &Piece) -> &[char] {
+        let buffer = match piece.source {
+            Source::Original => &self.original,
+            Source::Added => &self.added,
+        };
+
+        &buffer[piece.start..piece.start + piece.len]
+    }
+
+    /// Locates the piece containing `index`, returning its position in
+    /// `pieces` and the offset of `index` within that piece.
+    fn locate(&self, index: usize) -> (usize, usize) {
+        let mut remaining = index;
+
+        for (i, piece) in self.pieces.iter().enumerate() {
+            if remaining < piece.len {
+                return (i, remaining);
+            }
+
+            remaining -= piece.len;
+        }
+
+        (self.pieces.len(), 0)
+    }
+
+    pub fn char_at(&self, index: usize) -> char {
+        let (piece_idx, offset) = self.locate(index);
+        self.slice(&self.pieces[piece_idx])[offset]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = char> + '_ {
+        self.pieces
+            .iter()
+            .flat_map(|piece| self.slice(piece).iter().copied())
+    }
+
+    /// Collects the characters in `start..end`. Pieces before `start` are
+    /// stepped over without copying; the walk stops once it reaches `end`.
+    pub fn chars_range(&self, start: usize, end: usize) -> Vec<char> {
+        let mut result = Vec::with_capacity(end.saturating_sub(start));
+        let mut pos = 0;
+
+        for piece in &self.pieces {
+            let piece_end = pos + piece.len;
+
+            if piece_end > start && pos < end {
+                let from = start.saturating_sub(pos);
+                let to = (end.saturating_sub(pos)).min(piece.len);
+
+                result.extend_from_slice(&self.slice(piece)[from..to]);
+            }
+
+            pos = piece_end;
+
+            if pos >= end {
+                break;
+            }
+        }
+
+        result
+    }
+
+    /// Inserts `ch` before `index`, splitting at most one existing piece.
+    pub fn insert(&mut self, index: usize, ch: char) {
+        self.added.push(ch);
+        let added_start = self.added.len() - 1;
+
+        // Appending right after the previous insert just extends that
+        // piece in place instead of allocating a new one-char piece.
+        let at_end = index == self.len();
+
+        if let Some(last) = self.pieces.last_mut() {
+            if at_end && last.source == Source::Added && last.start + last.len == added_start {
+                last.len += 1;
+                return;
+            }
+        }
+
+        let (piece_idx, offset) = self.locate(index);
+
+        let new_piece = Piece {
+            source: Source::Added,
+            start: added_start,
+            len: 1,
+        };
+
+        if piece_idx == self.pieces.len() {
+            self.pieces.push(new_piece);
+            return;
+        }
+
+        if offset == 0 {
+            self.pieces.insert(piece_idx, new_piece);
+            return;
+        }
+
+        let piece = self.pieces[piece_idx];
+        let before = Piece {
+            source: piece.source,
+            start: piece.start,
+            len: offset,
+        };
+        let after = Piece {
+            source: piece.source,
+            start: piece.start + offset,
+            len: piece.len - offset,
+        };
+
+        self.pieces
+            .splice(piece_idx..piece_idx + 1, [before, new_piece, after]);
+    }
+
+    /// Removes and returns the character at `index`, trimming or dropping
+    /// the piece(s) spanning it.
+    pub fn remove(&mut self, index: usize) -> char {
+        let (piece_idx, offset) = self.locate(index);
+        let piece = self.pieces[piece_idx];
+        let ch = self.slice(&piece)[offset];
+
+        if piece.len == 1 {
+            self.pieces.remove(piece_idx);
+        } else if offset == 0 {
+            self.pieces[piece_idx].start += 1;
+            self.pieces[piece_idx].len -= 1;
+        } else if offset == piece.len - 1 {
+            self.pieces[piece_idx].len -= 1;
+        } else {
+            let before = Piece {
+                source: piece.source,
+                start: piece.start,
+                len: offset,
+            };
+            let after = Piece {
+                source: piece.source,
+                start: piece.start + offset + 1,
+                len: piece.len - offset - 1,
+            };
+
+            self.pieces
+                .splice(piece_idx..piece_idx + 1, [before, after]);
+        }
+
+        ch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PieceTable;
+
+    fn collect(table: &PieceTable) -> String {
+        table.iter().collect()
+    }
+
+    #[test]
+    fn insert_at_start() {
+        let mut table = PieceTable::new(&"world");
+        table.insert(0, ' ');
+        table.insert(0, 'o');
+        table.insert(0, 'l');
+        table.insert(0, 'l');
+        table.insert(0, 'e');
+        table.insert(0, 'h');
+
+        assert_eq!(collect(&table), "hello world");
+    }
+
+    #[test]
+    fn insert_at_end() {
+        let mut table = PieceTable::new(&"hello");
+        table.insert(5, ' ');
+        table.insert(6, 'w');
+        table.insert(7, 'o');
+        table.insert(8, 'r');
+        table.insert(9, 'l');
+        table.insert(10, 'd');
+
+        assert_eq!(collect(&table), "hello world");
+    }
+
+    #[test]
+    fn insert_in_middle_splits_a_piece() {
+        let mut table = PieceTable::new(&"helloworld");
+        table.insert(5, ' ');
+
+        assert_eq!(collect(&table), "hello world");
+        assert_eq!(table.len(), 11);
+    }
+
+    #[test]
+    fn remove_from_start_and_end() {
+        let mut table = PieceTable::new(&"hello");
+        assert_eq!(table.remove(0), 'h');
+        assert_eq!(collect(&table), "ello");
+
+        assert_eq!(table.remove(3), 'o');
+        assert_eq!(collect(&table), "ell");
+    }
+
+    #[test]
+    fn remove_splits_a_piece() {
+        let mut table = PieceTable::new(&"hello world");
+        assert_eq!(table.remove(5), ' ');
+
+        assert_eq!(collect(&table), "helloworld");
+        assert_eq!(table.len(), 10);
+    }
+
+    #[test]
+    fn char_at_and_chars_range() {
+        let table = PieceTable::new(&"hello world");
+
+        assert_eq!(table.char_at(6), 'w');
+        assert_eq!(
+            table.chars_range(6, 11).iter().collect::<String>(),
+            "world"
+        );
+    }
+
+    #[test]
+    fn round_trip_insert_and_remove() {
+        let mut table = PieceTable::new(&"abc");
+
+        table.insert(1, 'X');
+        assert_eq!(collect(&table), "aXbc");
+
+        table.insert(4, 'Y');
+        assert_eq!(collect(&table), "aXbcY");
+
+        assert_eq!(table.remove(1), 'X');
+        assert_eq!(collect(&table), "abcY");
+
+        assert_eq!(table.remove(3), 'Y');
+        assert_eq!(collect(&table), "abc");
+        assert_eq!(table.len(), 3);
+    }
+}